@@ -0,0 +1,10 @@
+// lib.rs
+//
+// Copyright (c) 2019, Univerisity of Minnesota
+//
+// Author: Bridger Herman (herma582@umn.edu)
+
+//! Vector and transform math for WRE.
+
+pub mod vec3;
+pub mod vec3a;
@@ -4,142 +4,308 @@
 //
 // Author: Bridger Herman (herma582@umn.edu)
 
-//! A 3 dimensional vector (mimicking GLM's vec3)
+//! A 3 dimensional vector (mimicking GLM's vec3), generic over both the
+//! scalar type and a phantom coordinate-space unit so e.g. world-space and
+//! local-space vectors can't be mixed by accident.
 
-use std::f32;
+use std::fmt;
+use std::marker::PhantomData;
 use std::ops::{Add, Mul, Neg, Sub};
 
-use num_traits::Zero;
+use num_traits::{Float, Zero};
+use serde::{Deserialize, Serialize};
 
-pub const MAX_VECTOR3: Vec3 = Vec3 {
-    x: f32::MAX,
-    y: f32::MAX,
-    z: f32::MAX,
-};
+/// The largest representable `Vector3D<T, U>`, component-wise.
+pub fn max_vector3<T: Float, U>() -> Vector3D<T, U> {
+    Vector3D::new(T::max_value(), T::max_value(), T::max_value())
+}
+
+/// Default unit for `Vector3D` when no particular coordinate space is being
+/// tracked, so existing call sites that just want `Vec3` compile unchanged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnknownUnit;
 
-/// 3 dimensional vector
-#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
-pub struct Vec3 {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
+/// 3 dimensional vector tagged with a coordinate-space unit `U`. Vectors in
+/// different units can't be added, subtracted, or dotted/crossed together;
+/// use `cast_unit` to explicitly cross a unit boundary (e.g. after applying
+/// a transform).
+///
+/// ```compile_fail
+/// use wre_transform::vec3::Vector3D;
+///
+/// struct World;
+/// struct Local;
+///
+/// let world_pos: Vector3D<f32, World> = Vector3D::new(1.0, 2.0, 3.0);
+/// let local_normal: Vector3D<f32, Local> = Vector3D::new(0.0, 1.0, 0.0);
+///
+/// // Mismatched units -- this must not compile.
+/// let _ = world_pos + local_normal;
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + serde::de::DeserializeOwned")]
+#[repr(C)]
+pub struct Vector3D<T, U = UnknownUnit> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    #[serde(skip)]
+    _unit: PhantomData<U>,
 }
 
-impl Zero for Vec3 {
-    fn zero() -> Self {
+/// Single-precision vector, the type most call sites use. This is a
+/// concrete alias (not generic over the unit) so unannotated construction
+/// like `Vec3::new(1.0, 2.0, 3.0)` still infers without help -- a default
+/// type parameter on an alias isn't consulted by inference in call
+/// position, only when an expected type flows in from elsewhere. Reach
+/// for `Vector3D<f32, U>` directly when you need a tagged unit.
+pub type Vec3 = Vector3D<f32, UnknownUnit>;
+
+/// Double-precision vector, for large scene extents or CAD-style
+/// coordinates where single precision accumulates too much error. See
+/// `Vec3` for why this isn't generic over the unit.
+pub type DVec3 = Vector3D<f64, UnknownUnit>;
+
+// `U` is a zero-sized marker, so these are implemented by hand rather than
+// derived -- a naive derive would require `U: Debug/PartialEq/Clone/Copy`
+// even though no unit marker ever needs to carry data.
+impl<T: fmt::Debug, U> fmt::Debug for Vector3D<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Vector3D")
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .field("z", &self.z)
+            .finish()
+    }
+}
+
+impl<T: PartialEq, U> PartialEq for Vector3D<T, U> {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x && self.y == other.y && self.z == other.z
+    }
+}
+
+impl<T: Clone, U> Clone for Vector3D<T, U> {
+    fn clone(&self) -> Self {
         Self {
-            x: 0.0,
-            y: 0.0,
-            z: 0.0,
+            x: self.x.clone(),
+            y: self.y.clone(),
+            z: self.z.clone(),
+            _unit: PhantomData,
         }
     }
+}
+
+impl<T: Copy, U> Copy for Vector3D<T, U> {}
+
+// SAFETY: `Vector3D<T, U>` is `#[repr(C)]` over three `T`s plus a
+// zero-sized `PhantomData<U>`, so it's safe to reinterpret as bytes
+// whenever `T` itself is.
+#[cfg(feature = "bytemuck")]
+unsafe impl<T: bytemuck::Pod, U: 'static> bytemuck::Pod for Vector3D<T, U> {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl<T: bytemuck::Zeroable, U> bytemuck::Zeroable for Vector3D<T, U> {}
+
+impl<T: Float, U> Zero for Vector3D<T, U> {
+    fn zero() -> Self {
+        Self::new(T::zero(), T::zero(), T::zero())
+    }
 
     fn is_zero(&self) -> bool {
-        self.x == 0.0 && self.y == 0.0 && self.z == 0.0
+        self.x.is_zero() && self.y.is_zero() && self.z.is_zero()
     }
 }
 
-impl<'a> From<&'a [f32]> for Vec3 {
-    fn from(slice: &'a [f32]) -> Self {
+impl<'a, T: Copy, U> From<&'a [T]> for Vector3D<T, U> {
+    fn from(slice: &'a [T]) -> Self {
         assert_eq!(slice.len(), 3);
-        Self {
-            x: slice[0],
-            y: slice[1],
-            z: slice[2],
-        }
+        Self::new(slice[0], slice[1], slice[2])
     }
 }
 
-impl Mul<f32> for Vec3 {
-    type Output = Vec3;
+impl<T: Float, U> Mul<T> for Vector3D<T, U> {
+    type Output = Vector3D<T, U>;
 
-    fn mul(self, scalar: f32) -> Self {
-        Self {
-            x: self.x * scalar,
-            y: self.y * scalar,
-            z: self.z * scalar,
-        }
+    fn mul(self, scalar: T) -> Self {
+        Self::new(self.x * scalar, self.y * scalar, self.z * scalar)
     }
 }
 
-impl Add<Vec3> for Vec3 {
-    type Output = Vec3;
+impl<T: Add<Output = T>, U> Add<Vector3D<T, U>> for Vector3D<T, U> {
+    type Output = Vector3D<T, U>;
 
-    fn add(self, other: Vec3) -> Self {
-        Self {
-            x: self.x + other.x,
-            y: self.y + other.y,
-            z: self.z + other.z,
-        }
+    fn add(self, other: Vector3D<T, U>) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
     }
 }
 
-impl Sub<Vec3> for Vec3 {
-    type Output = Vec3;
+impl<T: Sub<Output = T>, U> Sub<Vector3D<T, U>> for Vector3D<T, U> {
+    type Output = Vector3D<T, U>;
 
-    fn sub(self, other: Vec3) -> Self {
-        Self {
-            x: self.x - other.x,
-            y: self.y - other.y,
-            z: self.z - other.z,
-        }
+    fn sub(self, other: Vector3D<T, U>) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
     }
 }
 
-impl Neg for Vec3 {
-    type Output = Vec3;
+impl<T: Neg<Output = T>, U> Neg for Vector3D<T, U> {
+    type Output = Vector3D<T, U>;
 
     fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T, U> Vector3D<T, U> {
+    pub fn new(x: T, y: T, z: T) -> Self {
         Self {
-            x: -self.x,
-            y: -self.y,
-            z: -self.z,
+            x,
+            y,
+            z,
+            _unit: PhantomData,
         }
     }
+
+    /// Reinterpret this vector in a different unit, with no runtime cost.
+    /// Use this at explicit unit boundaries (e.g. right after a transform
+    /// produces a vector in its destination space).
+    pub fn cast_unit<V>(self) -> Vector3D<T, V> {
+        Vector3D::new(self.x, self.y, self.z)
+    }
 }
 
-impl Vec3 {
-    pub fn new(x: f32, y: f32, z: f32) -> Self {
-        Self { x, y, z }
+impl<T: Float, U> Vector3D<T, U> {
+    /// A vector with all three components set to `v`.
+    pub fn splat(v: T) -> Self {
+        Self::new(v, v, v)
     }
 
-    pub fn cross(&self, other: &Vec3) -> Self {
-        Self {
-            x: self.y * other.z - self.z * other.y,
-            y: self.z * other.x - self.x * other.z,
-            z: self.x * other.y - self.y * other.x,
-        }
+    /// The unit vector along X, i.e. glam's `Vec3::X`. `T` is generic here
+    /// (unlike glam's concrete `f32`), and `Float` isn't const-evaluable on
+    /// stable, so this is a function rather than an associated const --
+    /// named `unit_x` rather than `x` so it doesn't shadow the `x: T`
+    /// field (`v.x()` must keep meaning "call a method on the x field",
+    /// which it can't if an inherent `x()` associated function exists).
+    pub fn unit_x() -> Self {
+        Self::new(T::one(), T::zero(), T::zero())
+    }
+
+    /// The unit vector along Y, i.e. glam's `Vec3::Y`. See `unit_x` for why
+    /// this isn't named `y`.
+    pub fn unit_y() -> Self {
+        Self::new(T::zero(), T::one(), T::zero())
+    }
+
+    /// The unit vector along Z, i.e. glam's `Vec3::Z`. See `unit_x` for why
+    /// this isn't named `z`.
+    pub fn unit_z() -> Self {
+        Self::new(T::zero(), T::zero(), T::one())
+    }
+
+    /// `(1, 1, 1)`, i.e. glam's `Vec3::ONE`.
+    pub fn one() -> Self {
+        Self::splat(T::one())
+    }
+
+    /// `(-1, -1, -1)`, i.e. glam's `Vec3::NEG_ONE`.
+    pub fn neg_one() -> Self {
+        Self::splat(-T::one())
+    }
+
+    /// Component-wise minimum.
+    pub fn min(&self, other: &Self) -> Self {
+        Self::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    /// Component-wise maximum.
+    pub fn max(&self, other: &Self) -> Self {
+        Self::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
+    /// Component-wise clamp to `[min, max]`.
+    pub fn clamp(&self, min: &Self, max: &Self) -> Self {
+        self.max(min).min(max)
+    }
+
+    /// Linear interpolation between `self` and `other` by `t`.
+    pub fn lerp(self, other: Self, t: T) -> Self {
+        self + (other - self) * t
+    }
+
+    /// Hadamard (component-wise) product.
+    pub fn component_mul(&self, other: &Self) -> Self {
+        Self::new(self.x * other.x, self.y * other.y, self.z * other.z)
+    }
+
+    /// Hadamard (component-wise) quotient.
+    pub fn component_div(&self, other: &Self) -> Self {
+        Self::new(self.x / other.x, self.y / other.y, self.z / other.z)
+    }
+
+    /// Squared length. Cheaper than `length` when only comparing
+    /// magnitudes, since it skips the `sqrt`.
+    pub fn length_squared(&self) -> T {
+        self.dot(self)
+    }
+
+    pub fn distance(&self, other: &Self) -> T {
+        (*self - *other).length()
+    }
+
+    pub fn distance_squared(&self, other: &Self) -> T {
+        (*self - *other).length_squared()
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+
+    pub fn is_nan(&self) -> bool {
+        self.x.is_nan() || self.y.is_nan() || self.z.is_nan()
+    }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
     }
 
-    pub fn dot(&self, other: &Vec3) -> f32 {
+    pub fn dot(&self, other: &Self) -> T {
         self.x * other.x + self.y * other.y + self.z * other.z
     }
 
-    pub fn length(&self) -> f32 {
+    pub fn length(&self) -> T {
         self.dot(self).sqrt()
     }
 
     pub fn normalized(&self) -> Self {
         let length = self.length();
-        Self {
-            x: self.x / length,
-            y: self.y / length,
-            z: self.z / length,
-        }
+        Self::new(self.x / length, self.y / length, self.z / length)
     }
 
     pub fn reflect(self, normal: &Self) -> Self {
-        self - (*normal * self.dot(normal)) * 2.0
+        self - (*normal * self.dot(normal)) * (T::one() + T::one())
     }
 
     // Uses the same math from the GLM library - doesn't match GLM-rs
-    pub fn refract(self, normal: Self, eta: f32) -> Self {
+    pub fn refract(self, normal: Self, eta: T) -> Self {
         // Figure out if we're going into or coming out of the material
         let dot_ni = self.dot(&normal);
-        let eta = if dot_ni > 0.0 { eta } else { 1.0 / eta };
+        let eta = if dot_ni > T::zero() { eta } else { T::one() / eta };
 
-        let k = 1.0 - eta * eta * (1.0 - dot_ni * dot_ni);
-        if k < 0.0 {
+        let one = T::one();
+        let k = one - eta * eta * (one - dot_ni * dot_ni);
+        if k < T::zero() {
             self * eta
         } else {
             self * eta - normal * (eta * dot_ni + k.sqrt())
@@ -147,7 +313,215 @@ impl Vec3 {
         .normalized()
     }
 
-    pub fn angle(&self, other: &Self) -> f32 {
+    pub fn angle(&self, other: &Self) -> T {
         self.normalized().dot(&other.normalized()).acos()
     }
+
+    /// Per-component absolute difference.
+    pub fn abs_diff(&self, other: &Self) -> Self {
+        Self::new(
+            (self.x - other.x).abs(),
+            (self.y - other.y).abs(),
+            (self.z - other.z).abs(),
+        )
+    }
+
+    /// True if every component of `self` and `other` is within `epsilon`
+    /// (an absolute tolerance, not relative). Use this instead of
+    /// `PartialEq` after `normalized()`/`refract()`, where rounding drift
+    /// is expected.
+    pub fn approx_eq(&self, other: &Self, epsilon: T) -> bool {
+        let diff = self.abs_diff(other);
+        diff.x <= epsilon && diff.y <= epsilon && diff.z <= epsilon
+    }
+
+    /// `approx_eq` with a crate-wide default tolerance scaled off `T`'s
+    /// machine epsilon.
+    pub fn approx_eq_default(&self, other: &Self) -> bool {
+        let four = T::one() + T::one() + T::one() + T::one();
+        self.approx_eq(other, T::epsilon() * four)
+    }
+}
+
+// `mint` is the ecosystem's lingua franca for passing bare vectors across
+// crate boundaries (windowing, physics, gltf loaders). It has no notion of
+// our phantom unit, so the conversion is generic over `U` and just drops
+// the tag on the way out.
+//
+// The request that added this also asked for `mint::Point3` equivalents.
+// `wre-transform` doesn't have a `Point3` type of its own yet, so that
+// half is intentionally NOT delivered here rather than silently -- it's
+// follow-up work for whoever adds `Point3`, tracked against this request
+// (bridger-herman/wre-transform#chunk0-7), not a forgotten item.
+#[cfg(feature = "mint")]
+impl<U> From<mint::Vector3<f32>> for Vector3D<f32, U> {
+    fn from(v: mint::Vector3<f32>) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+#[cfg(feature = "mint")]
+impl<U> From<Vector3D<f32, U>> for mint::Vector3<f32> {
+    fn from(v: Vector3D<f32, U>) -> Self {
+        mint::Vector3 {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "bytemuck"))]
+mod bytemuck_tests {
+    use super::Vec3;
+
+    #[test]
+    fn cast_slice_roundtrips_through_bytes() {
+        let positions = vec![
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::new(4.0, 5.0, 6.0),
+            Vec3::new(-1.5, 0.0, 7.25),
+        ];
+
+        let bytes: &[u8] = bytemuck::cast_slice(&positions);
+        assert_eq!(bytes.len(), positions.len() * std::mem::size_of::<Vec3>());
+
+        let roundtripped: &[Vec3] = bytemuck::cast_slice(bytes);
+        assert_eq!(roundtripped, positions.as_slice());
+    }
+}
+
+#[cfg(all(test, feature = "mint"))]
+mod mint_tests {
+    use super::Vec3;
+
+    #[test]
+    fn roundtrips_through_mint_vector3() {
+        let v = Vec3::new(1.0, -2.5, 3.0);
+
+        let as_mint: mint::Vector3<f32> = v.into();
+        assert_eq!((as_mint.x, as_mint.y, as_mint.z), (1.0, -2.5, 3.0));
+
+        let back: Vec3 = as_mint.into();
+        assert_eq!(back, v);
+    }
+}
+
+#[cfg(test)]
+mod precision_tests {
+    use super::DVec3;
+
+    #[test]
+    fn dvec3_dot_cross_length_use_f64_precision() {
+        let a = DVec3::new(1.0, 0.0, 0.0);
+        let b = DVec3::new(0.0, 1.0, 0.0);
+
+        assert_eq!(a.dot(&b), 0.0);
+        assert_eq!(a.cross(&b), DVec3::new(0.0, 0.0, 1.0));
+
+        let big = DVec3::new(1e150, 2e150, 2e150);
+        // f64 keeps this finite where f32 (max ~3.4e38) would overflow.
+        assert!(big.length().is_finite());
+        assert!((big.x as f32).is_infinite());
+    }
+
+    #[test]
+    fn dvec3_normalized_has_unit_length() {
+        let v = DVec3::new(3.0, 4.0, 0.0);
+        assert_eq!(v.length(), 5.0);
+        assert!((v.normalized().length() - 1.0).abs() < f64::EPSILON * 4.0);
+    }
+}
+
+#[cfg(test)]
+mod helper_tests {
+    use super::Vec3;
+    use num_traits::Zero;
+
+    #[test]
+    fn lerp_interpolates_linearly() {
+        let a = Vec3::new(0.0, 0.0, 0.0);
+        let b = Vec3::new(10.0, 20.0, 30.0);
+
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 0.5), Vec3::new(5.0, 10.0, 15.0));
+    }
+
+    #[test]
+    fn min_max_clamp_are_componentwise() {
+        let a = Vec3::new(1.0, 5.0, -3.0);
+        let b = Vec3::new(4.0, 2.0, -1.0);
+
+        assert_eq!(a.min(&b), Vec3::new(1.0, 2.0, -3.0));
+        assert_eq!(a.max(&b), Vec3::new(4.0, 5.0, -1.0));
+
+        let v = Vec3::new(-5.0, 0.5, 10.0);
+        assert_eq!(v.clamp(&Vec3::zero(), &Vec3::one()), Vec3::new(0.0, 0.5, 1.0));
+    }
+
+    #[test]
+    fn component_mul_and_div_are_hadamard() {
+        let a = Vec3::new(2.0, 3.0, 4.0);
+        let b = Vec3::new(5.0, 6.0, 7.0);
+
+        assert_eq!(a.component_mul(&b), Vec3::new(10.0, 18.0, 28.0));
+        assert_eq!(b.component_div(&a), Vec3::new(2.5, 2.0, 1.75));
+    }
+
+    #[test]
+    fn distance_matches_length_of_difference() {
+        let a = Vec3::new(0.0, 0.0, 0.0);
+        let b = Vec3::new(3.0, 4.0, 0.0);
+
+        assert_eq!(a.distance(&b), 5.0);
+        assert_eq!(a.distance_squared(&b), 25.0);
+    }
+
+    #[test]
+    fn is_finite_and_is_nan() {
+        assert!(Vec3::new(1.0, 2.0, 3.0).is_finite());
+        assert!(!Vec3::new(f32::INFINITY, 0.0, 0.0).is_finite());
+        assert!(Vec3::new(f32::NAN, 0.0, 0.0).is_nan());
+        assert!(!Vec3::new(1.0, 2.0, 3.0).is_nan());
+    }
+}
+
+#[cfg(test)]
+mod approx_eq_tests {
+    use super::Vec3;
+
+    #[test]
+    fn abs_diff_is_per_component() {
+        let a = Vec3::new(1.0, -2.0, 3.0);
+        let b = Vec3::new(1.5, -2.25, 2.0);
+
+        let diff = a.abs_diff(&b);
+        assert_eq!(diff, Vec3::new(0.5, 0.25, 1.0));
+    }
+
+    #[test]
+    fn approx_eq_tolerates_rounding_drift_but_not_real_differences() {
+        let a = Vec3::new(1.0, 2.0, 3.0);
+        let close = Vec3::new(1.0001, 2.0001, 3.0001);
+        let far = Vec3::new(1.1, 2.0, 3.0);
+
+        assert!(a.approx_eq(&close, 0.001));
+        assert!(!a.approx_eq(&far, 0.001));
+    }
+
+    #[test]
+    fn approx_eq_default_survives_reflect_rounding() {
+        // Mirrors the motivating case from the request: a reflected ray
+        // should match its expected direction despite float rounding,
+        // which exact `PartialEq` would reject.
+        let incoming = Vec3::new(1.0, -1.0, 0.0).normalized();
+        let normal = Vec3::new(0.0, 1.0, 0.0);
+
+        let reflected = incoming.reflect(&normal);
+        let expected = Vec3::new(1.0, 1.0, 0.0).normalized();
+
+        assert!(reflected.approx_eq_default(&expected));
+        assert!(!reflected.approx_eq_default(&Vec3::new(0.0, 0.0, 0.0)));
+    }
 }
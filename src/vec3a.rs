@@ -0,0 +1,299 @@
+// vec3a.rs
+//
+// Copyright (c) 2019, Univerisity of Minnesota
+//
+// Author: Bridger Herman (herma582@umn.edu)
+
+//! A SIMD-backed 3 dimensional vector for hot-path math (ray batches,
+//! transform stacks). Prefer `Vec3` for storage; reach for `Vec3A` in tight
+//! loops and convert back at the boundary.
+//!
+//! Every intrinsic-backed item below is gated on the *same* predicate as
+//! the SIMD `Vec3A` definition (`x86`/`x86_64` **and** `target_feature =
+//! "sse2"`) -- gating a method on architecture alone while the struct
+//! itself also requires the `sse2` feature lets the two drift apart on an
+//! x86 target built without SSE2, where `Vec3A` falls back to the named
+//! scalar fields but an arch-only-gated method still assumes the
+//! tuple/`__m128` layout.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::vec3::Vec3;
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+use std::arch::x86_64::*;
+
+#[cfg(all(target_arch = "x86", target_feature = "sse2"))]
+use std::arch::x86::*;
+
+#[cfg(target_arch = "wasm32")]
+use std::arch::wasm32::*;
+
+/// 3 dimensional vector, 16-byte aligned and backed by a 128-bit SIMD
+/// register. The w lane is unused padding; x/y/z live in the low three
+/// lanes.
+#[cfg(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+    target_arch = "wasm32"
+))]
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct Vec3A(
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] __m128,
+    #[cfg(target_arch = "wasm32")] v128,
+);
+
+/// Scalar fallback for targets without a 128-bit SIMD register.
+#[cfg(not(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+    target_arch = "wasm32"
+)))]
+#[derive(Debug, Clone, Copy)]
+#[repr(align(16))]
+pub struct Vec3A {
+    x: f32,
+    y: f32,
+    z: f32,
+    _w: f32,
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+macro_rules! shuffle {
+    ($v:expr, $imm:expr) => {
+        _mm_shuffle_ps($v, $v, $imm)
+    };
+}
+
+impl Vec3A {
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        unsafe { Self(_mm_set_ps(0.0, z, y, x)) }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        Self(f32x4(x, y, z, 0.0))
+    }
+
+    #[cfg(not(any(
+        all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+        target_arch = "wasm32"
+    )))]
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z, _w: 0.0 }
+    }
+
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    pub fn x(&self) -> f32 {
+        unsafe { _mm_cvtss_f32(self.0) }
+    }
+
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    pub fn y(&self) -> f32 {
+        unsafe { _mm_cvtss_f32(shuffle!(self.0, 0b01_01_01_01)) }
+    }
+
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    pub fn z(&self) -> f32 {
+        unsafe { _mm_cvtss_f32(shuffle!(self.0, 0b10_10_10_10)) }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn x(&self) -> f32 {
+        f32x4_extract_lane::<0>(self.0)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn y(&self) -> f32 {
+        f32x4_extract_lane::<1>(self.0)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn z(&self) -> f32 {
+        f32x4_extract_lane::<2>(self.0)
+    }
+
+    #[cfg(not(any(
+        all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+        target_arch = "wasm32"
+    )))]
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+
+    #[cfg(not(any(
+        all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+        target_arch = "wasm32"
+    )))]
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+
+    #[cfg(not(any(
+        all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+        target_arch = "wasm32"
+    )))]
+    pub fn z(&self) -> f32 {
+        self.z
+    }
+
+    /// Dot product via a horizontal add of the low three lanes of the
+    /// product; the w lane is left out of the sum.
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    pub fn dot(&self, other: &Self) -> f32 {
+        unsafe {
+            let mul = _mm_mul_ps(self.0, other.0);
+            let shuf = shuffle!(mul, 0b00_00_00_01);
+            let sums = _mm_add_ss(mul, shuf);
+            let shuf2 = shuffle!(mul, 0b00_00_00_10);
+            let sums = _mm_add_ss(sums, shuf2);
+            _mm_cvtss_f32(sums)
+        }
+    }
+
+    #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2")))]
+    pub fn dot(&self, other: &Self) -> f32 {
+        self.x() * other.x() + self.y() * other.y() + self.z() * other.z()
+    }
+
+    /// Cross product via the standard two-shuffle trick: shuffle each
+    /// operand to both (y, z, x) and (z, x, y), multiply crosswise, and
+    /// subtract.
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    pub fn cross(&self, other: &Self) -> Self {
+        unsafe {
+            let a_yzx = shuffle!(self.0, 0b11_00_10_01);
+            let b_yzx = shuffle!(other.0, 0b11_00_10_01);
+            let a_zxy = shuffle!(self.0, 0b11_01_00_10);
+            let b_zxy = shuffle!(other.0, 0b11_01_00_10);
+            Self(_mm_sub_ps(_mm_mul_ps(a_yzx, b_zxy), _mm_mul_ps(a_zxy, b_yzx)))
+        }
+    }
+
+    #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2")))]
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::new(
+            self.y() * other.z() - self.z() * other.y(),
+            self.z() * other.x() - self.x() * other.z(),
+            self.x() * other.y() - self.y() * other.x(),
+        )
+    }
+}
+
+impl Add for Vec3A {
+    type Output = Vec3A;
+
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    fn add(self, other: Self) -> Self {
+        unsafe { Self(_mm_add_ps(self.0, other.0)) }
+    }
+
+    #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2")))]
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.x() + other.x(),
+            self.y() + other.y(),
+            self.z() + other.z(),
+        )
+    }
+}
+
+impl Sub for Vec3A {
+    type Output = Vec3A;
+
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    fn sub(self, other: Self) -> Self {
+        unsafe { Self(_mm_sub_ps(self.0, other.0)) }
+    }
+
+    #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2")))]
+    fn sub(self, other: Self) -> Self {
+        Self::new(
+            self.x() - other.x(),
+            self.y() - other.y(),
+            self.z() - other.z(),
+        )
+    }
+}
+
+impl Mul<f32> for Vec3A {
+    type Output = Vec3A;
+
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    fn mul(self, scalar: f32) -> Self {
+        unsafe { Self(_mm_mul_ps(self.0, _mm_set1_ps(scalar))) }
+    }
+
+    #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2")))]
+    fn mul(self, scalar: f32) -> Self {
+        Self::new(self.x() * scalar, self.y() * scalar, self.z() * scalar)
+    }
+}
+
+impl Neg for Vec3A {
+    type Output = Vec3A;
+
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    fn neg(self) -> Self {
+        unsafe { Self(_mm_sub_ps(_mm_setzero_ps(), self.0)) }
+    }
+
+    #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2")))]
+    fn neg(self) -> Self {
+        Self::new(-self.x(), -self.y(), -self.z())
+    }
+}
+
+impl From<Vec3> for Vec3A {
+    fn from(v: Vec3) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Vec3A> for Vec3 {
+    fn from(v: Vec3A) -> Self {
+        Vec3::new(v.x(), v.y(), v.z())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Vec3A;
+
+    #[test]
+    fn dot_of_orthogonal_basis_vectors_is_zero() {
+        let x = Vec3A::new(1.0, 0.0, 0.0);
+        let y = Vec3A::new(0.0, 1.0, 0.0);
+        assert_eq!(x.dot(&y), 0.0);
+    }
+
+    #[test]
+    fn dot_matches_scalar_definition() {
+        let a = Vec3A::new(1.0, 2.0, 3.0);
+        let b = Vec3A::new(4.0, 5.0, 6.0);
+        assert_eq!(a.dot(&b), 1.0 * 4.0 + 2.0 * 5.0 + 3.0 * 6.0);
+    }
+
+    #[test]
+    fn cross_of_x_and_y_is_z() {
+        let x = Vec3A::new(1.0, 0.0, 0.0);
+        let y = Vec3A::new(0.0, 1.0, 0.0);
+        let z = x.cross(&y);
+        assert_eq!((z.x(), z.y(), z.z()), (0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn cross_matches_scalar_definition() {
+        let a = Vec3A::new(1.0, 2.0, 3.0);
+        let b = Vec3A::new(4.0, 5.0, 6.0);
+        let c = a.cross(&b);
+        assert_eq!(
+            (c.x(), c.y(), c.z()),
+            (
+                2.0 * 6.0 - 3.0 * 5.0,
+                3.0 * 4.0 - 1.0 * 6.0,
+                1.0 * 5.0 - 2.0 * 4.0,
+            )
+        );
+    }
+}